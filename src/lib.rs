@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv6Addr};
 use std::time::Duration;
 #[cfg(feature = "worker-sdk")]
 use worker::Date;
@@ -26,8 +27,49 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, PartialEq)]
 pub enum Permit {
-    Allow(Option<Ticket>),
-    Deny,
+    Allow(Option<Ticket>, RateLimitInfo),
+    Deny(Duration, RateLimitInfo),
+}
+
+impl Permit {
+    /// IETF draft `RateLimit` headers (<https://datatracker.ietf.org/doc/draft-ietf-httpapi-ratelimit-headers/>)
+    /// describing the rule that was checked, plus `Retry-After` on denial.
+    pub fn headers(&self) -> Vec<(String, String)> {
+        let (info, retry_after) = match self {
+            Permit::Allow(_, info) => (info, None),
+            Permit::Deny(retry_after, info) => (info, Some(retry_after)),
+        };
+
+        let mut headers = vec![
+            ("RateLimit-Limit".to_string(), info.limit.to_string()),
+            ("RateLimit-Remaining".to_string(), info.remaining.to_string()),
+            ("RateLimit-Reset".to_string(), info.reset.as_secs().to_string()),
+        ];
+
+        if let Some(retry_after) = retry_after {
+            headers.push(("Retry-After".to_string(), retry_after.as_secs().to_string()));
+        }
+
+        headers
+    }
+}
+
+#[cfg(feature = "worker-sdk")]
+impl Permit {
+    pub fn apply_headers(&self, mut response: worker::Response) -> worker::Result<worker::Response> {
+        let headers = response.headers_mut();
+        for (name, value) in self.headers() {
+            headers.set(&name, &value)?;
+        }
+        Ok(response)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RateLimitInfo {
+    pub limit: u64,
+    pub remaining: u64,
+    pub reset: Duration,
 }
 
 pub type Stamp = BTreeMap<u64, u64>;
@@ -41,6 +83,15 @@ pub async fn fetch(kv: &KvStore, key: &str) -> Result<Stamp> {
     Ok(stamp)
 }
 
+async fn fetch_gcra(kv: &KvStore, key: &str) -> Result<Option<u64>> {
+    let tat = if let Some(bytes) = kv.get(key).bytes().await? {
+        Some(serde_json::from_slice::<u64>(&bytes)?)
+    } else {
+        None
+    };
+    Ok(tat)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Datetime {
     pub timestamp: u64,
@@ -55,51 +106,217 @@ impl Datetime {
 #[cfg(feature = "worker-sdk")]
 impl From<&Date> for Datetime {
     fn from(date: &Date) -> Self {
-        Self::from_timestamp(date.as_millis() / 1000)
+        Self::from_timestamp(date.as_millis())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GcraRule {
+    emission_interval: Duration,
+    tau: Duration,
+    amount: u64,
+}
+
+impl GcraRule {
+    fn new(period: Duration, amount: u64) -> Self {
+        if amount == 0 {
+            // nothing is ever allowed through, so the emission interval and
+            // burst tolerance are irrelevant; check_gcra short-circuits on
+            // `amount == 0` before they're used.
+            return Self {
+                emission_interval: Duration::from_millis(1),
+                tau: Duration::ZERO,
+                amount: 0,
+            };
+        }
+        // do the division in u128 nanos rather than casting `amount` to u32:
+        // `Duration::div(u32)` would panic on divide-by-zero for amounts that
+        // truncate to 0 (e.g. u32::MAX + 1), and use a wrong divisor for any
+        // other amount above u32::MAX
+        let emission_nanos = period.as_nanos() / amount as u128;
+        let tau_nanos = emission_nanos.saturating_mul((amount - 1) as u128);
+        let emission_interval = Duration::from_nanos(emission_nanos.min(u64::MAX as u128) as u64);
+        let tau = Duration::from_nanos(tau_nanos.min(u64::MAX as u128) as u64);
+        Self {
+            emission_interval,
+            tau,
+            amount,
+        }
+    }
+}
+
+enum Storage {
+    SlidingWindow(BTreeMap<Duration, u64>),
+    Gcra(Option<GcraRule>),
+}
+
+const DEFAULT_IPV6_PREFIX: u8 = 64;
+
+fn normalize_ip(ip_addr: &str, ipv6_prefix: u8) -> String {
+    match ip_addr.parse::<IpAddr>() {
+        Ok(IpAddr::V4(addr)) => addr.to_string(),
+        Ok(IpAddr::V6(addr)) => {
+            let mask = if ipv6_prefix == 0 {
+                0
+            } else if ipv6_prefix >= 128 {
+                u128::MAX
+            } else {
+                u128::MAX << (128 - ipv6_prefix)
+            };
+            let network = u128::from(addr) & mask;
+            Ipv6Addr::from(network).to_string()
+        }
+        Err(_) => ip_addr.to_string(),
     }
 }
 
 pub struct RateLimiter {
     pub prefix: String,
-    pub rules: BTreeMap<Duration, u64>,
+    ipv6_prefix: u8,
+    storage: Storage,
 }
 
 impl RateLimiter {
     pub fn new<I: Into<String>>(prefix: I) -> Self {
         Self {
             prefix: prefix.into(),
-            rules: BTreeMap::new(),
+            ipv6_prefix: DEFAULT_IPV6_PREFIX,
+            storage: Storage::SlidingWindow(BTreeMap::new()),
         }
     }
 
+    pub fn new_gcra<I: Into<String>>(prefix: I) -> Self {
+        Self {
+            prefix: prefix.into(),
+            ipv6_prefix: DEFAULT_IPV6_PREFIX,
+            storage: Storage::Gcra(None),
+        }
+    }
+
+    pub fn set_ipv6_prefix(&mut self, prefix: u8) {
+        self.ipv6_prefix = prefix;
+    }
+
     pub fn add_limit(&mut self, duration: Duration, amount: u64) {
-        self.rules.insert(duration, amount);
+        match &mut self.storage {
+            Storage::SlidingWindow(rules) => {
+                rules.insert(duration, amount);
+            }
+            Storage::Gcra(rule) => {
+                *rule = Some(GcraRule::new(duration, amount));
+            }
+        }
     }
 
     pub fn check_stamp<D: Into<Datetime>>(
         &self,
         stamp: &Stamp,
         now: D,
+        cost: u64,
     ) -> (Permit, Option<Duration>) {
+        let Storage::SlidingWindow(rules) = &self.storage else {
+            debug_assert!(false, "check_stamp called on a RateLimiter built with new_gcra");
+            return (Permit::Allow(None, RateLimitInfo::default()), None);
+        };
         let now = now.into();
 
         let mut max = None;
-        for (duration, amount) in &self.rules {
-            let start = now.timestamp - duration.as_secs();
+        let mut deny: Option<(Duration, RateLimitInfo)> = None;
+        let mut best_allow: Option<RateLimitInfo> = None;
+        for (duration, amount) in rules {
+            let duration_millis = duration.as_millis() as u64;
+            let start = now.timestamp.saturating_sub(duration_millis);
             let end = now.timestamp;
 
             let mut sum = 0;
-            for (_timestamp, num) in stamp.range(start..=end) {
+            let mut oldest = None;
+            for (timestamp, num) in stamp.range(start..=end) {
                 sum += num;
+                oldest.get_or_insert(*timestamp);
             }
 
-            if sum >= *amount {
-                return (Permit::Deny, None);
+            let reset_at = oldest
+                .unwrap_or(now.timestamp)
+                .saturating_add(duration_millis);
+            let info = RateLimitInfo {
+                limit: *amount,
+                remaining: amount.saturating_sub(sum),
+                reset: Duration::from_millis(reset_at.saturating_sub(now.timestamp)),
+            };
+
+            if sum.saturating_add(cost) > *amount {
+                let is_more_constraining = match &deny {
+                    Some((delay, _)) => info.reset > *delay,
+                    None => true,
+                };
+                if is_more_constraining {
+                    deny = Some((info.reset, info));
+                }
+                continue;
             }
 
+            if best_allow.as_ref().map_or(true, |b| info.remaining < b.remaining) {
+                best_allow = Some(info);
+            }
             max = Some(*duration);
         }
-        (Permit::Allow(None), max)
+
+        if let Some((delay, info)) = deny {
+            return (Permit::Deny(delay, info), None);
+        }
+        (Permit::Allow(None, best_allow.unwrap_or_default()), max)
+    }
+
+    pub fn check_gcra<D: Into<Datetime>>(
+        &self,
+        tat: Option<u64>,
+        now: D,
+        cost: u64,
+    ) -> (Permit, Option<u64>) {
+        let Storage::Gcra(rule) = &self.storage else {
+            debug_assert!(false, "check_gcra called on a RateLimiter built with new");
+            return (Permit::Allow(None, RateLimitInfo::default()), None);
+        };
+        let Some(rule) = rule else {
+            // no rule configured yet, nothing to enforce
+            return (Permit::Allow(None, RateLimitInfo::default()), None);
+        };
+        let now = now.into();
+
+        if rule.amount == 0 {
+            let info = RateLimitInfo::default();
+            return (Permit::Deny(Duration::ZERO, info), None);
+        }
+
+        let tat_prime = tat.unwrap_or(now.timestamp).max(now.timestamp);
+        let tau = rule.tau.as_millis() as u64;
+        // floor to 1ms: rules above 1000 req/s round down to a 0ms emission
+        // interval, which would stall `tat` at `now` and allow forever
+        let t = (rule.emission_interval.as_millis() as u64).max(1);
+        let limit = rule.amount;
+        // the request advances `tat` by `cost` emission intervals instead of
+        // just one, so a bulk request consumes `cost` units of the burst
+        let burst_offset = tau.saturating_add(t);
+        let new_tat = tat_prime.saturating_add(t.saturating_mul(cost));
+
+        if new_tat.saturating_sub(now.timestamp) > burst_offset {
+            let retry_at = new_tat.saturating_sub(burst_offset);
+            let delay = Duration::from_millis(retry_at.saturating_sub(now.timestamp));
+            let info = RateLimitInfo {
+                limit,
+                remaining: 0,
+                reset: delay,
+            };
+            (Permit::Deny(delay, info), None)
+        } else {
+            let used = new_tat.saturating_sub(now.timestamp);
+            let info = RateLimitInfo {
+                limit,
+                remaining: burst_offset.saturating_sub(used) / t,
+                reset: Duration::from_millis(t),
+            };
+            (Permit::Allow(None, info), Some(new_tat))
+        }
     }
 
     pub async fn check_kv<D: Into<Datetime>>(
@@ -107,53 +324,102 @@ impl RateLimiter {
         kv: &KvStore,
         ip_addr: &str,
         now: D,
+        cost: u64,
     ) -> Result<Permit> {
         let now = now.into();
-
-        let key = format!("{}/{}", self.prefix, ip_addr);
-        let stamp = fetch(kv, &key).await?;
-        let (mut permit, max) = self.check_stamp(&stamp, now);
-
-        // if the action is allowed, and there was at least one rule set, issue a ticket
-        if let (Permit::Allow(ticket), Some(max)) = (&mut permit, max) {
-            *ticket = Some(Ticket {
-                key,
-                datetime: now,
-                max,
-            });
+        let key = format!("{}/{}", self.prefix, normalize_ip(ip_addr, self.ipv6_prefix));
+
+        match &self.storage {
+            Storage::SlidingWindow(_) => {
+                let stamp = fetch(kv, &key).await?;
+                let (mut permit, max) = self.check_stamp(&stamp, now, cost);
+
+                // if the action is allowed, and there was at least one rule set, issue a ticket
+                if let (Permit::Allow(ticket, _), Some(max)) = (&mut permit, max) {
+                    *ticket = Some(Ticket::SlidingWindow {
+                        key,
+                        datetime: now,
+                        max,
+                    });
+                }
+
+                Ok(permit)
+            }
+            Storage::Gcra(Some(rule)) => {
+                let rule = *rule;
+                let tat = fetch_gcra(kv, &key).await?;
+                let (mut permit, new_tat) = self.check_gcra(tat, now, cost);
+
+                if let (Permit::Allow(ticket, _), Some(new_tat)) = (&mut permit, new_tat) {
+                    *ticket = Some(Ticket::Gcra {
+                        key,
+                        tat: new_tat,
+                        ttl: rule.tau + rule.emission_interval,
+                    });
+                }
+
+                Ok(permit)
+            }
+            Storage::Gcra(None) => Ok(Permit::Allow(None, RateLimitInfo::default())),
         }
-
-        Ok(permit)
     }
 }
 
+fn expire_stamp(datetime: Datetime, max: Duration, stamp: &mut Stamp) {
+    let cutoff = datetime.timestamp.saturating_sub(max.as_millis() as u64);
+    *stamp = stamp.split_off(&cutoff);
+}
+
 #[derive(Debug, PartialEq)]
-pub struct Ticket {
-    pub key: String,
-    pub datetime: Datetime,
-    pub max: Duration,
+pub enum Ticket {
+    SlidingWindow {
+        key: String,
+        datetime: Datetime,
+        max: Duration,
+    },
+    Gcra {
+        key: String,
+        tat: u64,
+        ttl: Duration,
+    },
 }
 
+// Cloudflare Workers KV rejects an `expirationTtl` below 60 seconds, so
+// sub-minute windows (e.g. 500ms) still need a 60s floor on the stored key
+const KV_MIN_EXPIRATION_TTL: u64 = 60;
+
 impl Ticket {
-    fn expire(&self, stamp: &mut Stamp) {
-        let cutoff = self.datetime.timestamp - self.max.as_secs();
-        *stamp = stamp.split_off(&cutoff);
+    pub async fn redeem(self, kv: &KvStore) -> Result<()> {
+        self.redeem_cost(kv, 1).await
     }
 
-    pub async fn redeem(self, kv: &KvStore) -> Result<()> {
-        let mut stamp = fetch(kv, &self.key).await?;
-        self.expire(&mut stamp);
+    pub async fn redeem_cost(self, kv: &KvStore, cost: u64) -> Result<()> {
+        match self {
+            Ticket::SlidingWindow { key, datetime, max } => {
+                let mut stamp = fetch(kv, &key).await?;
+                expire_stamp(datetime, max, &mut stamp);
 
-        let counter = stamp.entry(self.datetime.timestamp).or_default();
-        *counter = counter.saturating_add(1);
+                let counter = stamp.entry(datetime.timestamp).or_default();
+                *counter = counter.saturating_add(cost);
 
-        let bytes = serde_json::to_vec(&stamp)?;
-        kv.put_bytes(&self.key, &bytes)?
-            .expiration_ttl(self.max.as_secs() + 1)
-            .execute()
-            .await?;
+                let bytes = serde_json::to_vec(&stamp)?;
+                kv.put_bytes(&key, &bytes)?
+                    .expiration_ttl((max.as_secs() + 1).max(KV_MIN_EXPIRATION_TTL))
+                    .execute()
+                    .await?;
 
-        Ok(())
+                Ok(())
+            }
+            Ticket::Gcra { key, tat, ttl } => {
+                let bytes = serde_json::to_vec(&tat)?;
+                kv.put_bytes(&key, &bytes)?
+                    .expiration_ttl((ttl.as_secs() + 1).max(KV_MIN_EXPIRATION_TTL))
+                    .execute()
+                    .await?;
+
+                Ok(())
+            }
+        }
     }
 }
 
@@ -166,15 +432,40 @@ mod tests {
         let _: Option<worker_kv::KvStore> = Option::<worker::kv::KvStore>::None;
     }
 
+    #[test]
+    #[should_panic(expected = "check_stamp called on a RateLimiter built with new_gcra")]
+    fn test_stamp_check_panics_on_gcra_limiter() {
+        let limits = RateLimiter::new_gcra("ratelimit");
+        let stamp: Stamp = [].into_iter().collect();
+        limits.check_stamp(&stamp, Datetime::from_timestamp(0), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "check_gcra called on a RateLimiter built with new")]
+    fn test_gcra_check_panics_on_sliding_window_limiter() {
+        let limits = RateLimiter::new("ratelimit");
+        limits.check_gcra(None, Datetime::from_timestamp(0), 1);
+    }
+
     #[test]
     fn test_stamp_check_allow_empty() {
         let mut limits = RateLimiter::new("ratelimit");
         limits.add_limit(Duration::from_secs(5), 2);
 
         let stamp: Stamp = [].into_iter().collect();
-        let date = Datetime::from_timestamp(1710528366);
-        let (permit, _) = limits.check_stamp(&stamp, date);
-        assert_eq!(permit, Permit::Allow(None));
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, _) = limits.check_stamp(&stamp, date, 1);
+        assert_eq!(
+            permit,
+            Permit::Allow(
+                None,
+                RateLimitInfo {
+                    limit: 2,
+                    remaining: 2,
+                    reset: Duration::from_secs(5),
+                }
+            )
+        );
     }
 
     #[test]
@@ -182,10 +473,83 @@ mod tests {
         let mut limits = RateLimiter::new("ratelimit");
         limits.add_limit(Duration::from_secs(5), 2);
 
-        let stamp: Stamp = [(1710528362, 1)].into_iter().collect();
-        let date = Datetime::from_timestamp(1710528366);
-        let (permit, _) = limits.check_stamp(&stamp, date);
-        assert_eq!(permit, Permit::Allow(None));
+        let stamp: Stamp = [(1710528362000, 1)].into_iter().collect();
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, _) = limits.check_stamp(&stamp, date, 1);
+        assert_eq!(
+            permit,
+            Permit::Allow(
+                None,
+                RateLimitInfo {
+                    limit: 2,
+                    remaining: 1,
+                    reset: Duration::from_secs(1),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_stamp_check_cost_denies_when_exceeding_amount() {
+        let mut limits = RateLimiter::new("ratelimit");
+        limits.add_limit(Duration::from_secs(5), 10);
+
+        let stamp: Stamp = [(1710528362000, 3)].into_iter().collect();
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, _) = limits.check_stamp(&stamp, date, 8);
+        assert_eq!(
+            permit,
+            Permit::Deny(
+                Duration::from_secs(1),
+                RateLimitInfo {
+                    limit: 10,
+                    remaining: 7,
+                    reset: Duration::from_secs(1),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_stamp_check_sub_second_window() {
+        let mut limits = RateLimiter::new("ratelimit");
+        limits.add_limit(Duration::from_millis(500), 5);
+
+        let stamp: Stamp = [(1710528365800, 5)].into_iter().collect();
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, _) = limits.check_stamp(&stamp, date, 1);
+        assert_eq!(
+            permit,
+            Permit::Deny(
+                Duration::from_millis(300),
+                RateLimitInfo {
+                    limit: 5,
+                    remaining: 0,
+                    reset: Duration::from_millis(300),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_stamp_check_window_before_epoch_does_not_underflow() {
+        let mut limits = RateLimiter::new("ratelimit");
+        limits.add_limit(Duration::from_secs(5), 2);
+
+        let stamp: Stamp = [].into_iter().collect();
+        let date = Datetime::from_timestamp(2);
+        let (permit, _) = limits.check_stamp(&stamp, date, 1);
+        assert_eq!(
+            permit,
+            Permit::Allow(
+                None,
+                RateLimitInfo {
+                    limit: 2,
+                    remaining: 2,
+                    reset: Duration::from_secs(5),
+                }
+            )
+        );
     }
 
     #[test]
@@ -193,32 +557,266 @@ mod tests {
         let mut limits = RateLimiter::new("ratelimit");
         limits.add_limit(Duration::from_secs(5), 2);
 
-        let stamp: Stamp = [(1710528364, 1), (1710528363, 1)].into_iter().collect();
-        let date = Datetime::from_timestamp(1710528366);
-        let (permit, _) = limits.check_stamp(&stamp, date);
-        assert_eq!(permit, Permit::Deny);
+        let stamp: Stamp = [(1710528364000, 1), (1710528363000, 1)].into_iter().collect();
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, _) = limits.check_stamp(&stamp, date, 1);
+        assert_eq!(
+            permit,
+            Permit::Deny(
+                Duration::from_secs(2),
+                RateLimitInfo {
+                    limit: 2,
+                    remaining: 0,
+                    reset: Duration::from_secs(2),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_stamp_check_deny_uses_max_delay_across_rules() {
+        let mut limits = RateLimiter::new("ratelimit");
+        limits.add_limit(Duration::from_secs(5), 2);
+        limits.add_limit(Duration::from_secs(10), 3);
+
+        let stamp: Stamp = [(1710528360000, 2), (1710528363000, 1), (1710528365000, 1)]
+            .into_iter()
+            .collect();
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, _) = limits.check_stamp(&stamp, date, 1);
+        assert_eq!(
+            permit,
+            Permit::Deny(
+                Duration::from_secs(4),
+                RateLimitInfo {
+                    limit: 3,
+                    remaining: 0,
+                    reset: Duration::from_secs(4),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_permit_headers_allow() {
+        let permit = Permit::Allow(
+            None,
+            RateLimitInfo {
+                limit: 2,
+                remaining: 1,
+                reset: Duration::from_secs(5),
+            },
+        );
+        assert_eq!(
+            permit.headers(),
+            vec![
+                ("RateLimit-Limit".to_string(), "2".to_string()),
+                ("RateLimit-Remaining".to_string(), "1".to_string()),
+                ("RateLimit-Reset".to_string(), "5".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_permit_headers_deny() {
+        let permit = Permit::Deny(
+            Duration::from_secs(2),
+            RateLimitInfo {
+                limit: 2,
+                remaining: 0,
+                reset: Duration::from_secs(2),
+            },
+        );
+        assert_eq!(
+            permit.headers(),
+            vec![
+                ("RateLimit-Limit".to_string(), "2".to_string()),
+                ("RateLimit-Remaining".to_string(), "0".to_string()),
+                ("RateLimit-Reset".to_string(), "2".to_string()),
+                ("Retry-After".to_string(), "2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize_ip_v4_unchanged() {
+        assert_eq!(normalize_ip("203.0.113.7", 64), "203.0.113.7");
+    }
+
+    #[test]
+    fn test_normalize_ip_v6_masks_to_default_prefix() {
+        assert_eq!(
+            normalize_ip("2001:db8:1234:5678:aaaa:bbbb:cccc:dddd", 64),
+            "2001:db8:1234:5678::"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ip_v6_custom_prefix() {
+        assert_eq!(
+            normalize_ip("2001:db8:1234:5678:aaaa:bbbb:cccc:dddd", 48),
+            "2001:db8:1234::"
+        );
     }
 
     #[test]
     fn test_expire_stamp() {
         let mut stamp: Stamp = [
-            (1710550615, 3),
-            (1710550614, 4),
-            (1710550613, 7),
-            (1710550612, 1),
-            (1710550611, 9),
+            (1710550615000, 3),
+            (1710550614000, 4),
+            (1710550613000, 7),
+            (1710550612000, 1),
+            (1710550611000, 9),
         ]
         .into_iter()
         .collect();
-        let ticket = Ticket {
-            key: "abc".to_string(),
-            datetime: Datetime::from_timestamp(1710550643),
-            max: Duration::from_secs(30),
-        };
-        ticket.expire(&mut stamp);
-        let expected: Stamp = [(1710550615, 3), (1710550614, 4), (1710550613, 7)]
+        let datetime = Datetime::from_timestamp(1710550643000);
+        let max = Duration::from_secs(30);
+        expire_stamp(datetime, max, &mut stamp);
+        let expected: Stamp = [(1710550615000, 3), (1710550614000, 4), (1710550613000, 7)]
             .into_iter()
             .collect();
         assert_eq!(stamp, expected);
     }
+
+    #[test]
+    fn test_gcra_check_allow_empty() {
+        let mut limits = RateLimiter::new_gcra("ratelimit");
+        limits.add_limit(Duration::from_secs(10), 2);
+
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, tat) = limits.check_gcra(None, date, 1);
+        assert_eq!(
+            permit,
+            Permit::Allow(
+                None,
+                RateLimitInfo {
+                    limit: 2,
+                    remaining: 1,
+                    reset: Duration::from_secs(5),
+                }
+            )
+        );
+        assert_eq!(tat, Some(1710528371000));
+    }
+
+    #[test]
+    fn test_gcra_check_allow_within_burst() {
+        let mut limits = RateLimiter::new_gcra("ratelimit");
+        limits.add_limit(Duration::from_secs(10), 2);
+
+        let date = Datetime::from_timestamp(1710528368000);
+        let (permit, tat) = limits.check_gcra(Some(1710528371000), date, 1);
+        assert_eq!(
+            permit,
+            Permit::Allow(
+                None,
+                RateLimitInfo {
+                    limit: 2,
+                    remaining: 0,
+                    reset: Duration::from_secs(5),
+                }
+            )
+        );
+        assert_eq!(tat, Some(1710528376000));
+    }
+
+    #[test]
+    fn test_gcra_check_deny() {
+        let mut limits = RateLimiter::new_gcra("ratelimit");
+        limits.add_limit(Duration::from_secs(10), 2);
+
+        let date = Datetime::from_timestamp(1710528368000);
+        let (permit, tat) = limits.check_gcra(Some(1710528376000), date, 1);
+        assert_eq!(
+            permit,
+            Permit::Deny(
+                Duration::from_secs(3),
+                RateLimitInfo {
+                    limit: 2,
+                    remaining: 0,
+                    reset: Duration::from_secs(3),
+                }
+            )
+        );
+        assert_eq!(tat, None);
+    }
+
+    #[test]
+    fn test_gcra_check_zero_amount_denies_without_panic() {
+        let mut limits = RateLimiter::new_gcra("ratelimit");
+        limits.add_limit(Duration::from_secs(1), 0);
+
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, tat) = limits.check_gcra(None, date, 1);
+        assert_eq!(permit, Permit::Deny(Duration::ZERO, RateLimitInfo::default()));
+        assert_eq!(tat, None);
+    }
+
+    #[test]
+    fn test_gcra_check_amount_above_u32_max_does_not_panic() {
+        let mut limits = RateLimiter::new_gcra("ratelimit");
+        limits.add_limit(Duration::from_secs(10), u32::MAX as u64 + 1);
+
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, _) = limits.check_gcra(None, date, 1);
+        assert!(matches!(permit, Permit::Allow(..)));
+    }
+
+    #[test]
+    fn test_gcra_check_floors_emission_interval_for_high_rate_rules() {
+        let mut limits = RateLimiter::new_gcra("ratelimit");
+        limits.add_limit(Duration::from_secs(1), 2000);
+
+        let date = Datetime::from_timestamp(1710528366000);
+        let mut tat = None;
+        let mut denied = false;
+        for _ in 0..5000 {
+            let (permit, new_tat) = limits.check_gcra(tat, date, 1);
+            if matches!(permit, Permit::Deny(..)) {
+                denied = true;
+                break;
+            }
+            tat = new_tat;
+        }
+
+        // before flooring the emission interval to >= 1ms, `tat` never advanced past
+        // `now` for rules above 1000 req/s, so this loop ran all its iterations without denying
+        assert!(denied, "expected the burst to eventually be denied");
+    }
+
+    #[test]
+    fn test_gcra_check_cost_consumes_multiple_units() {
+        let mut limits = RateLimiter::new_gcra("ratelimit");
+        limits.add_limit(Duration::from_secs(10), 4);
+
+        let date = Datetime::from_timestamp(1710528366000);
+        let (permit, tat) = limits.check_gcra(None, date, 3);
+        assert_eq!(
+            permit,
+            Permit::Allow(
+                None,
+                RateLimitInfo {
+                    limit: 4,
+                    remaining: 1,
+                    reset: Duration::from_millis(2500),
+                }
+            )
+        );
+        assert_eq!(tat, Some(1710528373500));
+
+        let (permit, tat) = limits.check_gcra(tat, date, 2);
+        assert_eq!(
+            permit,
+            Permit::Deny(
+                Duration::from_millis(2500),
+                RateLimitInfo {
+                    limit: 4,
+                    remaining: 0,
+                    reset: Duration::from_millis(2500),
+                }
+            )
+        );
+        assert_eq!(tat, None);
+    }
 }